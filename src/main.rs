@@ -1,7 +1,127 @@
 use nix::sys::signal::{self, Signal, SigSet};
 use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
-use nix::unistd::Pid;
+use nix::unistd::{isatty, setpgid, tcsetpgrp, Pid};
+use std::os::unix::process::{CommandExt, ExitStatusExt};
 use std::process::{Child, Command, ExitCode, ExitStatus};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Signal that suspends the child, sent by default on `SIGUSR1`.
+const DEFAULT_STOP_SIGNAL: Signal = Signal::SIGUSR1;
+/// Signal that restarts the child, sent by default on `SIGUSR2`.
+const DEFAULT_RESUME_SIGNAL: Signal = Signal::SIGUSR2;
+/// Signal sent to ask the child to shut down cleanly before we escalate to SIGKILL.
+const DEFAULT_TERM_SIGNAL: Signal = Signal::SIGTERM;
+/// How long to wait for the child to exit after the term signal before killing it.
+const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+/// Overrides `DEFAULT_GRACE_PERIOD`, in milliseconds.
+const GRACE_PERIOD_ENV_VAR: &str = "SUSPENDABLE_INIT_GRACE_PERIOD_MS";
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Parsed command-line configuration: which signals drive suspend/resume/graceful
+/// termination, and the child command to run.
+#[derive(Debug)]
+struct Config {
+    stop_signal: Signal,
+    resume_signal: Signal,
+    term_signal: Signal,
+    command: String,
+    command_args: Vec<String>,
+}
+
+fn parse_signal(value: &str) -> Result<Signal, String> {
+    if let Ok(num) = value.parse::<i32>() {
+        return Signal::try_from(num).map_err(|_| format!("'{}' is not a valid signal number", num));
+    }
+    value
+        .parse::<Signal>()
+        .map_err(|_| format!("'{}' is not a valid signal name", value))
+}
+
+/// `stop_signal`/`resume_signal` must not collide with a signal suspendable-init treats
+/// specially: `SIGCHLD` (child-exit detection), whose arm is matched ahead of the
+/// configured stop/resume arms, so a collision would make the stop/resume flag silently
+/// unreachable; or `SIGINT`/`SIGTERM` (the force-quit escape hatch while suspended),
+/// whose arm is matched after them, so a collision would make that escape hatch
+/// unreachable.
+fn validate_signal_choice(flag: &str, signal: Signal) -> Result<(), String> {
+    if matches!(signal, Signal::SIGCHLD | Signal::SIGINT | Signal::SIGTERM) {
+        return Err(format!(
+            "{} cannot be {:?}: it collides with a signal suspendable-init treats specially",
+            flag, signal
+        ));
+    }
+    Ok(())
+}
+
+/// Parses `--stop-signal`, `--resume-signal`, and `--term-signal` (each accepting a
+/// signal name like `SIGHUP` or a number), followed by an optional `--` separator and
+/// the child command. Everything from the command onward, including further `--`-style
+/// tokens, is passed through untouched as the child's argv.
+fn parse_args_from<I: Iterator<Item = String>>(mut args: I) -> Result<Config, String> {
+    let mut stop_signal = DEFAULT_STOP_SIGNAL;
+    let mut resume_signal = DEFAULT_RESUME_SIGNAL;
+    let mut term_signal = DEFAULT_TERM_SIGNAL;
+
+    let mut command = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--stop-signal" => {
+                let value = args.next().ok_or("--stop-signal requires a value")?;
+                stop_signal = parse_signal(&value)?;
+            }
+            "--resume-signal" => {
+                let value = args.next().ok_or("--resume-signal requires a value")?;
+                resume_signal = parse_signal(&value)?;
+            }
+            "--term-signal" => {
+                let value = args.next().ok_or("--term-signal requires a value")?;
+                term_signal = parse_signal(&value)?;
+            }
+            "--" => {
+                command = args.next();
+                break;
+            }
+            other => {
+                command = Some(other.to_string());
+                break;
+            }
+        }
+    }
+
+    validate_signal_choice("--stop-signal", stop_signal)?;
+    validate_signal_choice("--resume-signal", resume_signal)?;
+    if stop_signal == resume_signal {
+        return Err(format!(
+            "--stop-signal and --resume-signal cannot both be {:?}: the resume arm would never be reached",
+            stop_signal
+        ));
+    }
+
+    let command = command.ok_or("No command provided")?;
+    let command_args: Vec<String> = args.collect();
+
+    Ok(Config {
+        stop_signal,
+        resume_signal,
+        term_signal,
+        command,
+        command_args,
+    })
+}
+
+fn parse_args() -> Result<Config, String> {
+    parse_args_from(std::env::args().skip(1))
+}
+
+fn grace_period() -> Duration {
+    std::env::var(GRACE_PERIOD_ENV_VAR)
+        .ok()
+        .and_then(|val| val.parse::<u64>().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_GRACE_PERIOD)
+}
 
 fn reap_zombies() {
     loop {
@@ -17,41 +137,109 @@ fn reap_zombies() {
     }
 }
 
-extern "C" fn handle_signal(_sig: i32) {
-    reap_zombies();
+/// Puts `pgid` in the foreground of our controlling TTY, if we have one, so
+/// interactive job-control signals (e.g. Ctrl-C) reach the child's group directly.
+fn put_in_foreground(pgid: Pid) {
+    let tty = std::io::stdin();
+    if isatty(&tty).unwrap_or(false) {
+        let _ = tcsetpgrp(&tty, pgid);
+    }
+}
+
+/// Spawns the child as the leader of a new process group (via `setpgid`, not `setsid`,
+/// so it stays in our session and `put_in_foreground` can still hand it the controlling
+/// TTY), so signals can be forwarded to its entire process tree rather than just the
+/// direct child. Returns the child along with its pgid (equal to its own pid, since it
+/// is the group leader).
+fn spawn_child_process(command: &str, args: &[String]) -> Result<(Child, Pid), std::io::Error> {
+    let child = unsafe {
+        Command::new(command)
+            .args(args)
+            .pre_exec(|| {
+                setpgid(Pid::from_raw(0), Pid::from_raw(0))
+                    .map_err(|e| std::io::Error::from_raw_os_error(e as i32))?;
+                Ok(())
+            })
+            .spawn()
+            .expect("Failed to start application")
+    };
+    let pgid = Pid::from_raw(child.id().try_into().expect("child pid does not fit in pid_t"));
+    put_in_foreground(pgid);
+    Ok((child, pgid))
 }
 
-fn setup_signal_handlers() {
-    unsafe {
-        signal::signal(Signal::SIGCHLD, signal::SigHandler::Handler(handle_signal)).unwrap();
+fn send_signal(pid: Pid, signal: Signal) {
+    if let Err(e) = signal::kill(pid, signal) {
+        match e {
+            nix::errno::Errno::ESRCH => {
+                eprintln!("Cannot send {:?}: no such process {:?}", signal, pid)
+            }
+            nix::errno::Errno::EINVAL => {
+                eprintln!("Cannot send {:?} to {:?}: invalid signal", signal, pid)
+            }
+            nix::errno::Errno::EPERM => {
+                eprintln!("Cannot send {:?} to {:?}: permission denied", signal, pid)
+            }
+            e => eprintln!("Failed to send {:?} to {:?}: {:?}", signal, pid, e),
+        }
     }
 }
 
-fn spawn_child_process() -> Result<Child, std::io::Error> {
-    let command = std::env::args().nth(1).expect("No command provided");
-    let args: Vec<String> = std::env::args().skip(2).collect();
-    let child = Command::new(command)
-        .args(args)
-        .spawn()
-        .expect("Failed to start application");
-    Ok(child)
+/// Ask the child to exit via `term_signal`, giving it up to `grace_period` to do so
+/// before escalating to `SIGKILL`. This avoids the abrupt termination of a plain
+/// `Child::kill`, which always sends `SIGKILL` and gives the child no chance to clean up.
+fn terminate_gracefully(proc: &mut Child, pgid: Pid, term_signal: Signal, grace_period: Duration) {
+    let group = Pid::from_raw(-pgid.as_raw());
+
+    send_signal(group, term_signal);
+
+    let deadline = Instant::now() + grace_period;
+    loop {
+        match proc.try_wait() {
+            Ok(Some(_)) => return,
+            Ok(None) => {
+                if Instant::now() >= deadline {
+                    break;
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+            Err(e) => {
+                eprintln!("Error polling child process: {}", e);
+                return;
+            }
+        }
+    }
+
+    send_signal(group, Signal::SIGKILL);
 }
 
-fn exit_status_to_exit_code(status: ExitStatus) -> ExitCode {
+/// Encodes `status` as a shell-style exit code: a normal exit keeps its own code, while a
+/// signal-terminated status is mapped to 128+signum (e.g. SIGKILL (9) -> 137, SIGTERM
+/// (15) -> 143), matching the convention used by other process wrappers.
+fn exit_status_to_code(status: ExitStatus) -> u8 {
     if let Some(code) = status.code() {
-        ExitCode::from(code as u8)
+        code as u8
     } else {
-        // This can happen if the process was terminated by a signal
-        // Here we choose a generic exit code, like 1, to indicate an error
-        ExitCode::from(1)
+        let signum = status.signal().expect("ExitStatus has neither code nor signal");
+        128u8.wrapping_add(signum as u8)
     }
 }
 
+fn exit_status_to_exit_code(status: ExitStatus) -> ExitCode {
+    ExitCode::from(exit_status_to_code(status))
+}
+
 fn main() -> ExitCode {
-    setup_signal_handlers();
+    let config = match parse_args() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
 
     let mut running = true;
-    let mut proc = match spawn_child_process() {
+    let (mut proc, mut pgid) = match spawn_child_process(&config.command, &config.command_args) {
         Ok(child) => child,
         Err(e) => {
             eprintln!("Failed to start child process: {}", e);
@@ -66,28 +254,29 @@ fn main() -> ExitCode {
         println!("{:?}", signal);
         match signal {
             Signal::SIGCHLD => {
+                // `proc.try_wait()` reaps the tracked child in the same call that decides
+                // whether it exited, so there is no gap in which the WNOHANG drain below
+                // could steal its status before we get to see it.
                 match proc.try_wait() {
-                    Ok(Some(status)) if running => {
+                    Ok(Some(status)) => {
                         reap_zombies();
-                        return exit_status_to_exit_code(status);
+                        if running {
+                            return exit_status_to_exit_code(status);
+                        }
                     },
-                    Ok(_) => {
-                        reap_zombies();
-                    },
-                    Err(_) => {
-                        return ExitCode::FAILURE;
-                    }
+                    Ok(None) => reap_zombies(),
+                    Err(_) => return ExitCode::FAILURE,
                 }
             },
-            Signal::SIGUSR1 => {
+            s if s == config.stop_signal => {
                 running = false;
-                let _ = proc.kill();
+                terminate_gracefully(&mut proc, pgid, config.term_signal, grace_period());
             },
-            Signal::SIGUSR2 => {
+            s if s == config.resume_signal => {
                 running = true;
-                let _ = proc.kill();
+                terminate_gracefully(&mut proc, pgid, config.term_signal, grace_period());
                 sigset.thread_unblock().expect("Failed to unblock signals");
-                proc = match spawn_child_process() {
+                (proc, pgid) = match spawn_child_process(&config.command, &config.command_args) {
                     Ok(child) => child,
                     Err(e) => {
                         eprintln!("Failed to start child process: {}", e);
@@ -100,14 +289,135 @@ fn main() -> ExitCode {
                 return ExitCode::SUCCESS;
             },
             _ => {
-                if let Ok(pid) = proc.id().try_into() {
-                    let pid = Pid::from_raw(pid);
-                    println!("Sending {:?} to {:?}", signal, pid);
-                    let _ = signal::kill(pid, signal).expect("Error sending signal to process");
+                // The child may have exited between signal delivery and us processing
+                // it here, in which case its pid/pgid could be recycled by the kernel.
+                // Re-check liveness immediately before forwarding so we never signal an
+                // unrelated, reused pgid.
+                match proc.try_wait() {
+                    Ok(Some(status)) => {
+                        reap_zombies();
+                        if running {
+                            return exit_status_to_exit_code(status);
+                        }
+                    },
+                    Ok(None) => {
+                        let group = Pid::from_raw(-pgid.as_raw());
+                        println!("Sending {:?} to group {:?}", signal, group);
+                        send_signal(group, signal);
+                    },
+                    Err(e) => {
+                        eprintln!("Error checking child process before forwarding {:?}: {:?}", signal, e);
+                    }
                 }
             }
         }
     }
 
     ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> impl Iterator<Item = String> {
+        values.iter().map(|s| s.to_string()).collect::<Vec<_>>().into_iter()
+    }
+
+    #[test]
+    fn defaults_when_no_flags_given() {
+        let config = parse_args_from(args(&["mycmd", "--arg"])).unwrap();
+        assert_eq!(config.stop_signal, DEFAULT_STOP_SIGNAL);
+        assert_eq!(config.resume_signal, DEFAULT_RESUME_SIGNAL);
+        assert_eq!(config.term_signal, DEFAULT_TERM_SIGNAL);
+        assert_eq!(config.command, "mycmd");
+        assert_eq!(config.command_args, vec!["--arg".to_string()]);
+    }
+
+    #[test]
+    fn parses_signal_name() {
+        let config = parse_args_from(args(&["--stop-signal", "SIGHUP", "mycmd"])).unwrap();
+        assert_eq!(config.stop_signal, Signal::SIGHUP);
+    }
+
+    #[test]
+    fn parses_signal_number() {
+        let config = parse_args_from(args(&["--resume-signal", "12", "mycmd"])).unwrap();
+        assert_eq!(config.resume_signal, Signal::SIGUSR2);
+    }
+
+    #[test]
+    fn rejects_invalid_signal_name() {
+        let err = parse_args_from(args(&["--term-signal", "NOTASIGNAL", "mycmd"])).unwrap_err();
+        assert!(err.contains("not a valid signal name"));
+    }
+
+    #[test]
+    fn rejects_invalid_signal_number() {
+        let err = parse_args_from(args(&["--term-signal", "999", "mycmd"])).unwrap_err();
+        assert!(err.contains("not a valid signal number"));
+    }
+
+    #[test]
+    fn rejects_missing_flag_value() {
+        let err = parse_args_from(args(&["--stop-signal"])).unwrap_err();
+        assert_eq!(err, "--stop-signal requires a value");
+    }
+
+    #[test]
+    fn rejects_missing_command() {
+        let err = parse_args_from(args(&["--stop-signal", "SIGHUP"])).unwrap_err();
+        assert_eq!(err, "No command provided");
+    }
+
+    #[test]
+    fn double_dash_separates_command_from_flags() {
+        let config = parse_args_from(args(&["--", "--stop-signal", "mycmd"])).unwrap();
+        assert_eq!(config.command, "--stop-signal");
+        assert_eq!(config.command_args, vec!["mycmd".to_string()]);
+    }
+
+    #[test]
+    fn rejects_stop_signal_colliding_with_force_quit_signal() {
+        let err = parse_args_from(args(&["--stop-signal", "SIGTERM", "mycmd"])).unwrap_err();
+        assert!(err.contains("--stop-signal"));
+    }
+
+    #[test]
+    fn rejects_resume_signal_colliding_with_sigchld() {
+        let err = parse_args_from(args(&["--resume-signal", "SIGCHLD", "mycmd"])).unwrap_err();
+        assert!(err.contains("--resume-signal"));
+    }
+
+    #[test]
+    fn rejects_stop_and_resume_signal_being_equal() {
+        let err = parse_args_from(args(&[
+            "--stop-signal",
+            "SIGHUP",
+            "--resume-signal",
+            "SIGHUP",
+            "mycmd",
+        ]))
+        .unwrap_err();
+        assert!(err.contains("--stop-signal"));
+        assert!(err.contains("--resume-signal"));
+    }
+
+    #[test]
+    fn sigterm_maps_to_143() {
+        let status = ExitStatus::from_raw(Signal::SIGTERM as i32);
+        assert_eq!(exit_status_to_code(status), 143);
+    }
+
+    #[test]
+    fn sigkill_maps_to_137() {
+        let status = ExitStatus::from_raw(Signal::SIGKILL as i32);
+        assert_eq!(exit_status_to_code(status), 137);
+    }
+
+    #[test]
+    fn normal_exit_code_is_preserved() {
+        let status = ExitStatus::from_raw(7 << 8);
+        assert_eq!(exit_status_to_code(status), 7);
+    }
 }
\ No newline at end of file